@@ -0,0 +1,185 @@
+/*
+ * git-chronicler
+ *
+ * Copyright (C) 2025 Giuseppe Scrivano <giuseppe@scrivano.org>
+ * git-chronicler is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * git-chronicler is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with git-chronicler.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+use crate::conventional;
+use crate::extract_message;
+use crate::run_git_command;
+use codehawk::openai::{Opts, ToolsCollection, make_message, post_request};
+use log::{debug, info};
+use std::error::Error;
+use std::fs;
+use std::io::Write as _;
+use std::os::unix::fs::PermissionsExt;
+use std::process::Command;
+
+/// A commit whose message was improved by the model.
+pub struct RewrittenCommit {
+    pub hash: String,
+    pub old_message: String,
+    pub new_message: String,
+}
+
+fn list_commits(range: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    let out = run_git_command(vec!["rev-list", "--no-merges", "--reverse", range])?;
+    Ok(out
+        .lines()
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty())
+        .collect())
+}
+
+fn commit_message(hash: &str) -> Result<String, Box<dyn Error>> {
+    run_git_command(vec!["log", "-1", "--pretty=format:%B", hash])
+}
+
+fn commit_patch(hash: &str) -> Result<String, Box<dyn Error>> {
+    run_git_command(vec!["log", "-p", "-1", hash])
+}
+
+/// Improves the message of every non-merge commit in `range`, one AI request per commit,
+/// each scoped to that commit's own patch. When `conventional_types` is `Some`, each
+/// rewritten message is validated (and retried once) as a Conventional Commit the same
+/// way the single-commit `Fixup` path does, so `--range --conventional` never rewrites
+/// history with a malformed message.
+pub fn improve_range(
+    range: &str,
+    prompt: &str,
+    style_prompt: &str,
+    tools: &ToolsCollection,
+    query_opts: &Opts,
+    conventional_types: Option<&[&str]>,
+) -> Result<Vec<RewrittenCommit>, Box<dyn Error>> {
+    let hashes = list_commits(range)?;
+    info!("Improving {} commits in {}", hashes.len(), range);
+
+    let mut rewritten = vec![];
+    for hash in hashes {
+        debug!("Improving commit {}", hash);
+        let old_message = commit_message(&hash)?;
+        let patch = commit_patch(&hash)?;
+
+        let messages = vec![
+            make_message("system", patch.clone()),
+            make_message("system", style_prompt.to_string()),
+            make_message("user", prompt.to_string()),
+        ];
+
+        let response = post_request(messages, tools, query_opts)?;
+        let new_message = extract_message(
+            response,
+            &format!("no AI response received for commit {}", hash),
+        )?;
+
+        let new_message = match conventional_types {
+            Some(allowed_types) => {
+                let system_prompts = vec![patch, style_prompt.to_string()];
+                let (validated, _) = conventional::ensure_conventional(
+                    new_message,
+                    &system_prompts,
+                    prompt,
+                    tools,
+                    query_opts,
+                    allowed_types,
+                )
+                .map_err(|e| format!("commit {}: {}", hash, e))?;
+                validated
+            }
+            None => new_message,
+        };
+
+        rewritten.push(RewrittenCommit {
+            hash,
+            old_message,
+            new_message,
+        });
+    }
+
+    Ok(rewritten)
+}
+
+/// Picks a heredoc terminator that doesn't occur as a whole line in `message`, so an
+/// AI-generated message can never terminate its own heredoc early.
+fn heredoc_marker(message: &str) -> String {
+    let mut marker = "CHRONICLER_MSG_EOF".to_string();
+    while message.lines().any(|line| line == marker) {
+        marker.push('_');
+    }
+    marker
+}
+
+/// Rewrites history so each commit in `rewritten` gets its new message, keyed by commit
+/// hash, preserving authorship, dates and trailers for everything it doesn't touch.
+pub fn apply(range: &str, rewritten: &[RewrittenCommit]) -> Result<(), Box<dyn Error>> {
+    let mut script = String::from("#!/bin/sh\ncase \"$GIT_COMMIT\" in\n");
+    for commit in rewritten {
+        let marker = heredoc_marker(&commit.new_message);
+        script.push_str(&format!(
+            "  {})\n    cat <<'{}'\n{}\n{}\n    ;;\n",
+            commit.hash, marker, commit.new_message, marker
+        ));
+    }
+    script.push_str("  *) cat ;;\nesac\n");
+
+    let mut script_file = tempfile::NamedTempFile::new()?;
+    script_file.write_all(script.as_bytes())?;
+    let script_path = script_file
+        .path()
+        .to_str()
+        .ok_or("invalid temp file name")?
+        .to_string();
+
+    let mut perms = fs::metadata(&script_path)?.permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(&script_path, perms)?;
+
+    let status = Command::new("git")
+        .args(["filter-branch", "-f", "--msg-filter", &script_path, range])
+        .status()?;
+
+    if !status.success() {
+        return Err("git filter-branch failed while rewriting commit messages".into());
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn heredoc_marker_uses_default_when_no_collision() {
+        assert_eq!(heredoc_marker("fix: a normal message\n\nwith a body"), "CHRONICLER_MSG_EOF");
+    }
+
+    #[test]
+    fn heredoc_marker_avoids_colliding_with_message_content() {
+        let message = "feat: add thing\n\nCHRONICLER_MSG_EOF\n\nmore text";
+        let marker = heredoc_marker(message);
+        assert_ne!(marker, "CHRONICLER_MSG_EOF");
+        assert!(!message.lines().any(|line| line == marker));
+    }
+
+    #[test]
+    fn heredoc_marker_handles_repeated_collisions() {
+        let message = "CHRONICLER_MSG_EOF\nCHRONICLER_MSG_EOF_\nCHRONICLER_MSG_EOF__";
+        let marker = heredoc_marker(message);
+        assert!(!message.lines().any(|line| line == marker));
+    }
+}
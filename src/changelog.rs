@@ -0,0 +1,348 @@
+/*
+ * git-chronicler
+ *
+ * Copyright (C) 2025 Giuseppe Scrivano <giuseppe@scrivano.org>
+ * git-chronicler is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * git-chronicler is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with git-chronicler.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+use crate::conventional::{self, ConventionalCommit};
+use crate::extract_message;
+use crate::run_git_command;
+use codehawk::openai::{Opts, ToolsCollection, make_message, post_request};
+use log::debug;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+use std::io;
+
+/// Default marker under which `write_to_file` inserts the generated section.
+pub const DEFAULT_MARKER: &str = "<!-- chronicler:changelog -->";
+
+/// Headings rendered in order, each mapped from its Conventional Commit type.
+const SECTION_ORDER: &[(&str, &str)] = &[
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance Improvements"),
+    ("revert", "Reverts"),
+    ("docs", "Documentation"),
+    ("refactor", "Code Refactoring"),
+    ("build", "Build System"),
+    ("ci", "Continuous Integration"),
+    ("test", "Tests"),
+    ("style", "Styles"),
+    ("chore", "Chores"),
+];
+
+struct CommitEntry {
+    hash: String,
+    subject: String,
+    body: String,
+}
+
+/// Finds the most recent tag reachable from `to`, used as the default `from` ref.
+fn find_last_tag(to: &str) -> Option<String> {
+    run_git_command(vec!["describe", "--tags", "--abbrev=0", to])
+        .ok()
+        .map(|s| s.trim().to_string())
+}
+
+fn collect_commits(from: &str, to: &str) -> Result<Vec<CommitEntry>, Box<dyn Error>> {
+    let range = format!("{}..{}", from, to);
+    let out = run_git_command(vec![
+        "log",
+        "--no-merges",
+        "--pretty=format:%H%x01%s%x01%b%x00",
+        &range,
+    ])?;
+
+    let commits = out
+        .split('\0')
+        .filter(|c| !c.trim().is_empty())
+        .map(|c| {
+            let mut parts = c.splitn(3, '\u{1}');
+            let hash = parts.next().unwrap_or("").trim().to_string();
+            let subject = parts.next().unwrap_or("").trim().to_string();
+            let body = parts.next().unwrap_or("").trim().to_string();
+            CommitEntry { hash, subject, body }
+        })
+        .collect();
+
+    Ok(commits)
+}
+
+fn short_hash(hash: &str) -> &str {
+    &hash[..7.min(hash.len())]
+}
+
+fn bullet(entry: &CommitEntry, parsed: &ConventionalCommit) -> String {
+    match &parsed.scope {
+        Some(scope) => format!(
+            "* **{}:** {} ({})",
+            scope,
+            parsed.description,
+            short_hash(&entry.hash)
+        ),
+        None => format!("* {} ({})", parsed.description, short_hash(&entry.hash)),
+    }
+}
+
+/// Builds the single "BREAKING CHANGES" bullet for a commit already known to be
+/// breaking, preferring the `BREAKING CHANGE:` footer text over the header
+/// description so a commit with both doesn't end up listed twice.
+fn breaking_bullet(entry: &CommitEntry, parsed: &ConventionalCommit) -> String {
+    parsed
+        .footers
+        .iter()
+        .find(|(token, _)| token.eq_ignore_ascii_case("BREAKING CHANGE"))
+        .map(|(_, value)| format!("* {} ({})", value, short_hash(&entry.hash)))
+        .unwrap_or_else(|| bullet(entry, parsed))
+}
+
+/// Synthesizes a short changelog bullet for a commit that doesn't parse as a
+/// Conventional Commit, reusing the existing `post_request` flow.
+fn summarize_with_ai(
+    entry: &CommitEntry,
+    style_prompt: &str,
+    tools: &ToolsCollection,
+    query_opts: &Opts,
+) -> Result<String, Box<dyn Error>> {
+    debug!("Summarizing non-conventional commit {} for changelog", entry.hash);
+    let prompt = format!(
+        "Write a single short changelog bullet point (one line, starting with '* ') \
+         summarizing this commit for a release.  Do not add any other text:\n\n{}\n\n{}",
+        entry.subject, entry.body
+    );
+
+    let messages = vec![
+        make_message("system", style_prompt.to_string()),
+        make_message("user", prompt),
+    ];
+
+    let response = post_request(messages, tools, query_opts)?;
+    let text = extract_message(
+        response,
+        "No responses received while summarizing commit for changelog",
+    )?;
+
+    let text = text.trim();
+    if text.starts_with('*') {
+        Ok(format!("{} ({})", text, short_hash(&entry.hash)))
+    } else {
+        Ok(format!("* {} ({})", text, short_hash(&entry.hash)))
+    }
+}
+
+/// Builds the Markdown changelog section for commits in `from..to`, grouped by
+/// Conventional Commit type, with a dedicated BREAKING CHANGES block.
+pub fn generate(
+    from: Option<String>,
+    to: Option<String>,
+    allowed_types: &[&str],
+    style_prompt: &str,
+    tools: &ToolsCollection,
+    query_opts: &Opts,
+) -> Result<String, Box<dyn Error>> {
+    let to = to.unwrap_or_else(|| "HEAD".to_string());
+    let from = from
+        .or_else(|| find_last_tag(&to))
+        .ok_or("no 'from' ref given and no tag found to default to")?;
+
+    debug!("Generating changelog for {}..{}", from, to);
+    let commits = collect_commits(&from, &to)?;
+
+    let mut sections: HashMap<&'static str, Vec<String>> = HashMap::new();
+    let mut breaking: Vec<String> = vec![];
+    let mut others: Vec<String> = vec![];
+
+    for entry in &commits {
+        let full_message = format!("{}\n\n{}", entry.subject, entry.body);
+        match conventional::parse(&full_message, allowed_types) {
+            Ok(parsed) => {
+                if parsed.breaking {
+                    breaking.push(breaking_bullet(entry, &parsed));
+                }
+                match SECTION_ORDER.iter().find(|(ty, _)| *ty == parsed.commit_type) {
+                    Some((_, heading)) => {
+                        sections.entry(heading).or_default().push(bullet(entry, &parsed));
+                    }
+                    None => others.push(bullet(entry, &parsed)),
+                }
+            }
+            Err(_) => {
+                others.push(summarize_with_ai(entry, style_prompt, tools, query_opts)?);
+            }
+        }
+    }
+
+    let mut out = String::new();
+    if !breaking.is_empty() {
+        out.push_str("### BREAKING CHANGES\n\n");
+        out.push_str(&breaking.join("\n"));
+        out.push_str("\n\n");
+    }
+
+    for (_, heading) in SECTION_ORDER {
+        if let Some(bullets) = sections.get(heading) {
+            out.push_str(&format!("### {}\n\n", heading));
+            out.push_str(&bullets.join("\n"));
+            out.push_str("\n\n");
+        }
+    }
+
+    if !others.is_empty() {
+        out.push_str("### Other Changes\n\n");
+        out.push_str(&others.join("\n"));
+        out.push_str("\n\n");
+    }
+
+    Ok(out.trim_end().to_string())
+}
+
+/// Writes `section` into `path`, inserting it right after `marker`. If `marker` is not
+/// found, it is added at the top of the file together with the new section.
+pub fn write_to_file(path: &str, marker: &str, section: &str) -> Result<(), Box<dyn Error>> {
+    let existing = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(e.into()),
+    };
+
+    let updated = match existing.find(marker) {
+        Some(pos) => {
+            let insert_at = pos + marker.len();
+            format!(
+                "{}\n\n{}\n{}",
+                &existing[..insert_at],
+                section,
+                &existing[insert_at..]
+            )
+        }
+        None => format!("{}\n\n{}\n\n{}", marker, section, existing),
+    };
+
+    fs::write(path, updated)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(hash: &str) -> CommitEntry {
+        CommitEntry {
+            hash: hash.to_string(),
+            subject: String::new(),
+            body: String::new(),
+        }
+    }
+
+    #[test]
+    fn bullet_without_scope() {
+        let e = entry("abcdef1234567");
+        let parsed = conventional::parse("fix: correct off-by-one error", &["fix"]).unwrap();
+        assert_eq!(bullet(&e, &parsed), "* correct off-by-one error (abcdef1)");
+    }
+
+    #[test]
+    fn bullet_with_scope() {
+        let e = entry("abcdef1234567");
+        let parsed = conventional::parse("feat(api): add widgets endpoint", &["feat"]).unwrap();
+        assert_eq!(
+            bullet(&e, &parsed),
+            "* **api:** add widgets endpoint (abcdef1)"
+        );
+    }
+
+    #[test]
+    fn breaking_bullet_prefers_footer_over_header_description() {
+        let e = entry("abcdef1234567");
+        let parsed = conventional::parse(
+            "feat!: drop the v1 endpoints\n\nBREAKING CHANGE: removed old endpoints entirely",
+            &["feat"],
+        )
+        .unwrap();
+        assert_eq!(
+            breaking_bullet(&e, &parsed),
+            "* removed old endpoints entirely (abcdef1)"
+        );
+    }
+
+    #[test]
+    fn breaking_bullet_falls_back_to_header_description_without_footer() {
+        let e = entry("abcdef1234567");
+        let parsed = conventional::parse("feat!: drop the v1 endpoints", &["feat"]).unwrap();
+        assert_eq!(breaking_bullet(&e, &parsed), "* drop the v1 endpoints (abcdef1)");
+    }
+
+    #[test]
+    fn short_hash_truncates_to_seven_chars() {
+        assert_eq!(short_hash("abcdef1234567890"), "abcdef1");
+    }
+
+    #[test]
+    fn short_hash_keeps_shorter_hashes_as_is() {
+        assert_eq!(short_hash("abc"), "abc");
+    }
+
+    #[test]
+    fn write_to_file_inserts_after_existing_marker() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("CHANGELOG.md");
+        fs::write(&path, "# Changelog\n\n<!-- chronicler:changelog -->\n\n## v1.0.0\n").unwrap();
+
+        write_to_file(path.to_str().unwrap(), "<!-- chronicler:changelog -->", "### Features\n\n* thing").unwrap();
+
+        let updated = fs::read_to_string(&path).unwrap();
+        assert!(updated.contains("<!-- chronicler:changelog -->\n\n### Features\n\n* thing"));
+        assert!(updated.contains("## v1.0.0"));
+    }
+
+    #[test]
+    fn write_to_file_prepends_marker_and_section_when_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("CHANGELOG.md");
+        fs::write(&path, "# Changelog\n").unwrap();
+
+        write_to_file(path.to_str().unwrap(), "<!-- chronicler:changelog -->", "### Features\n\n* thing").unwrap();
+
+        let updated = fs::read_to_string(&path).unwrap();
+        assert!(updated.starts_with("<!-- chronicler:changelog -->\n\n### Features\n\n* thing"));
+        assert!(updated.contains("# Changelog"));
+    }
+
+    #[test]
+    fn write_to_file_creates_file_when_missing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("CHANGELOG.md");
+
+        write_to_file(path.to_str().unwrap(), "<!-- chronicler:changelog -->", "### Features\n\n* thing").unwrap();
+
+        let updated = fs::read_to_string(&path).unwrap();
+        assert!(updated.starts_with("<!-- chronicler:changelog -->\n\n### Features\n\n* thing"));
+    }
+
+    #[test]
+    fn write_to_file_propagates_non_not_found_read_errors() {
+        // Reading a directory as a file fails with an error other than NotFound;
+        // write_to_file must surface it instead of treating it as an empty file
+        // and clobbering whatever is actually there.
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("CHANGELOG.md");
+        fs::create_dir(&path).unwrap();
+
+        assert!(write_to_file(path.to_str().unwrap(), "<!-- chronicler:changelog -->", "### Features\n\n* thing")
+            .is_err());
+    }
+}
@@ -0,0 +1,285 @@
+/*
+ * git-chronicler
+ *
+ * Copyright (C) 2025 Giuseppe Scrivano <giuseppe@scrivano.org>
+ * git-chronicler is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * git-chronicler is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with git-chronicler.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+use crate::run_git_command;
+use log::debug;
+use serde::Deserialize;
+use std::fs;
+
+const CONFIG_FILE_NAME: &str = ".chronicler.toml";
+
+/// Shape of the optional `.chronicler.toml` repo config file.
+#[derive(Debug, Default, Deserialize)]
+struct TomlConfig {
+    model: Option<String>,
+    endpoint: Option<String>,
+    max_tokens: Option<u32>,
+    base: Option<String>,
+    conventional: Option<bool>,
+    conventional_types: Option<Vec<String>>,
+    changelog_marker: Option<String>,
+    forge: Option<TomlForge>,
+    prompts: Option<TomlPrompts>,
+}
+
+/// Shape of the `[forge]` block in `.chronicler.toml`.
+#[derive(Debug, Default, Deserialize)]
+struct TomlForge {
+    #[serde(rename = "type")]
+    kind: Option<String>,
+    endpoint: Option<String>,
+    token_env: Option<String>,
+}
+
+/// Prompt overrides that can be set in `.chronicler.toml`.
+#[derive(Debug, Default, Deserialize)]
+struct TomlPrompts {
+    inline: Option<String>,
+    write: Option<String>,
+    check: Option<String>,
+    summary: Option<String>,
+}
+
+/// Settings resolved with precedence CLI flag > `git config` > `.chronicler.toml` > built-in default.
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub model: String,
+    pub endpoint: String,
+    pub max_tokens: u32,
+    /// Default base branch for `Summary`, if none was given on the command line.
+    pub base: Option<String>,
+    /// Whether `Write`/`Fixup` should enforce Conventional Commits by default.
+    pub conventional: bool,
+    /// Allowed Conventional Commit types, if overridden.
+    pub conventional_types: Option<Vec<String>>,
+    /// Marker in `CHANGELOG.md` under which `changelog --write` inserts new sections.
+    pub changelog_marker: Option<String>,
+    /// Git forge to publish branch summaries to, if configured.
+    pub forge: Option<ForgeConfig>,
+    pub inline_prompt: Option<String>,
+    pub write_prompt: Option<String>,
+    pub check_prompt: Option<String>,
+    pub summary_prompt: Option<String>,
+}
+
+/// Git forge settings used by `Summary --publish`.
+#[derive(Debug, Clone)]
+pub struct ForgeConfig {
+    /// Backend to use: `github` or `gitea`.
+    pub kind: String,
+    /// API base URL. Required for `gitea`, defaults to the public GitHub API otherwise.
+    pub endpoint: Option<String>,
+    /// Access token, read from the environment or `git config`.
+    pub token: Option<String>,
+}
+
+fn default_token_env(kind: &str) -> String {
+    match kind {
+        "github" => "GITHUB_TOKEN".to_string(),
+        "gitea" => "GITEA_TOKEN".to_string(),
+        _ => "CHRONICLER_FORGE_TOKEN".to_string(),
+    }
+}
+
+/// Reads `chronicler.<key>` from `git config`, returning `None` if it is unset.
+pub fn git_config_get(key: &str, config_type: Option<&str>) -> Option<String> {
+    let full_key = format!("chronicler.{}", key);
+    let mut args = vec!["config", "--get"];
+    if let Some(t) = config_type {
+        args.push("--type");
+        args.push(t);
+    }
+    args.push(&full_key);
+
+    match run_git_command(args) {
+        Ok(value) => {
+            let trimmed = value.trim();
+            if trimmed.is_empty() {
+                None
+            } else {
+                Some(trimmed.to_string())
+            }
+        }
+        Err(_) => {
+            debug!("No git config value set for chronicler.{}", key);
+            None
+        }
+    }
+}
+
+/// Resolves `.chronicler.toml`'s path at the repository root, regardless of the
+/// current working directory, the same way `hooks::hooks_dir` resolves the
+/// hooks directory via `git rev-parse`.
+fn config_file_path() -> String {
+    match run_git_command(vec!["rev-parse", "--show-toplevel"]) {
+        Ok(toplevel) => format!("{}/{}", toplevel.trim(), CONFIG_FILE_NAME),
+        Err(_) => CONFIG_FILE_NAME.to_string(),
+    }
+}
+
+fn load_toml_config() -> TomlConfig {
+    let path = config_file_path();
+    match fs::read_to_string(&path) {
+        Ok(content) => toml::from_str(&content).unwrap_or_else(|e| {
+            debug!("Failed to parse {}: {}", path, e);
+            TomlConfig::default()
+        }),
+        Err(_) => {
+            debug!("No {} found, using defaults", path);
+            TomlConfig::default()
+        }
+    }
+}
+
+/// Resolves the effective configuration, giving CLI flags priority over `git config`,
+/// which in turn takes priority over `.chronicler.toml`, which takes priority over defaults.
+pub fn load(cli_model: Option<String>, cli_endpoint: Option<String>, cli_max_tokens: Option<u32>) -> Config {
+    let toml_config = load_toml_config();
+
+    let model = cli_model
+        .or_else(|| git_config_get("model", None))
+        .or(toml_config.model)
+        .unwrap_or_else(|| crate::MODEL.to_string());
+
+    let endpoint = cli_endpoint
+        .or_else(|| git_config_get("endpoint", None))
+        .or(toml_config.endpoint)
+        .unwrap_or_else(|| crate::DEFAULT_OPENAI_URL.to_string());
+
+    let max_tokens = cli_max_tokens
+        .or_else(|| {
+            git_config_get("max-tokens", Some("int")).and_then(|v| v.parse::<u32>().ok())
+        })
+        .or(toml_config.max_tokens)
+        .unwrap_or(crate::MAX_TOKENS);
+
+    let base = git_config_get("base", None).or(toml_config.base);
+
+    let conventional = git_config_get("conventional", Some("bool"))
+        .map(|v| v == "true")
+        .or(toml_config.conventional)
+        .unwrap_or(false);
+
+    let conventional_types = git_config_get("conventional-types", None)
+        .map(|v| v.split(',').map(|t| t.trim().to_string()).collect())
+        .or(toml_config.conventional_types);
+
+    let changelog_marker = git_config_get("changelog-marker", None).or(toml_config.changelog_marker);
+
+    let forge = git_config_get("forge-type", None)
+        .or_else(|| toml_config.forge.as_ref().and_then(|f| f.kind.clone()))
+        .map(|kind| {
+            let endpoint = git_config_get("forge-endpoint", None)
+                .or_else(|| toml_config.forge.as_ref().and_then(|f| f.endpoint.clone()));
+            let token_env = git_config_get("forge-token-env", None)
+                .or_else(|| toml_config.forge.as_ref().and_then(|f| f.token_env.clone()))
+                .unwrap_or_else(|| default_token_env(&kind));
+            let token = std::env::var(&token_env)
+                .ok()
+                .or_else(|| git_config_get("forge-token", None));
+            ForgeConfig { kind, endpoint, token }
+        });
+
+    let (inline_prompt, write_prompt, check_prompt, summary_prompt) = match toml_config.prompts {
+        Some(p) => (p.inline, p.write, p.check, p.summary),
+        None => (None, None, None, None),
+    };
+
+    Config {
+        model,
+        endpoint,
+        max_tokens,
+        base,
+        conventional,
+        conventional_types,
+        changelog_marker,
+        forge,
+        inline_prompt,
+        write_prompt,
+        check_prompt,
+        summary_prompt,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_token_env_known_kinds() {
+        assert_eq!(default_token_env("github"), "GITHUB_TOKEN");
+        assert_eq!(default_token_env("gitea"), "GITEA_TOKEN");
+    }
+
+    #[test]
+    fn default_token_env_falls_back_for_unknown_kind() {
+        assert_eq!(default_token_env("bitbucket"), "CHRONICLER_FORGE_TOKEN");
+    }
+
+    #[test]
+    fn toml_config_parses_all_fields() {
+        let toml = r#"
+            model = "gpt-5"
+            endpoint = "https://example.com/v1"
+            max_tokens = 4096
+            base = "main"
+            conventional = true
+            conventional_types = ["feat", "fix", "security"]
+            changelog_marker = "<!-- marker -->"
+
+            [forge]
+            type = "gitea"
+            endpoint = "https://gitea.example.com/api/v1"
+            token_env = "MY_TOKEN"
+
+            [prompts]
+            inline = "inline prompt"
+            write = "write prompt"
+            check = "check prompt"
+            summary = "summary prompt"
+        "#;
+
+        let config: TomlConfig = toml::from_str(toml).unwrap();
+        assert_eq!(config.model.as_deref(), Some("gpt-5"));
+        assert_eq!(config.max_tokens, Some(4096));
+        assert_eq!(config.conventional, Some(true));
+        assert_eq!(
+            config.conventional_types,
+            Some(vec!["feat".to_string(), "fix".to_string(), "security".to_string()])
+        );
+        let forge = config.forge.unwrap();
+        assert_eq!(forge.kind.as_deref(), Some("gitea"));
+        assert_eq!(forge.token_env.as_deref(), Some("MY_TOKEN"));
+        let prompts = config.prompts.unwrap();
+        assert_eq!(prompts.inline.as_deref(), Some("inline prompt"));
+    }
+
+    #[test]
+    fn toml_config_defaults_when_empty() {
+        let config: TomlConfig = toml::from_str("").unwrap();
+        assert!(config.model.is_none());
+        assert!(config.forge.is_none());
+        assert!(config.prompts.is_none());
+    }
+
+    #[test]
+    fn git_config_get_returns_none_for_unset_key() {
+        assert_eq!(git_config_get("not-a-real-chronicler-key", None), None);
+    }
+}
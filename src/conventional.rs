@@ -0,0 +1,239 @@
+/*
+ * git-chronicler
+ *
+ * Copyright (C) 2025 Giuseppe Scrivano <giuseppe@scrivano.org>
+ * git-chronicler is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * git-chronicler is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with git-chronicler.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+use crate::extract_message;
+use codehawk::openai::{Message, Opts, ToolsCollection, make_message, post_request};
+use log::debug;
+use regex::Regex;
+use std::error::Error;
+
+/// The commit types accepted when no `chronicler.conventional-types` override is configured.
+pub const DEFAULT_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "chore", "build", "ci", "revert",
+];
+
+/// A git commit message parsed as a Conventional Commit.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConventionalCommit {
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub description: String,
+    pub body: Option<String>,
+    pub footers: Vec<(String, String)>,
+}
+
+/// Parses `msg` as a Conventional Commit header + optional body + footers:
+/// `type(scope)!: description`, a blank line, an optional body, then `Token: value`
+/// or `Token #value` footers (`BREAKING CHANGE:` marks the commit as breaking).
+///
+/// Returns a human-readable error describing what is wrong with `msg` so it can be
+/// fed back to the model for a retry.
+pub fn parse(msg: &str, allowed_types: &[&str]) -> Result<ConventionalCommit, String> {
+    let msg = msg.trim();
+    let mut parts = msg.splitn(2, "\n\n");
+    let header = parts.next().unwrap_or("").trim();
+    let remainder = parts.next().unwrap_or("").trim();
+
+    let header_re = Regex::new(
+        r"^(?P<type>[A-Za-z]+)(\((?P<scope>[^)]+)\))?(?P<breaking>!)?: (?P<description>.+)$",
+    )
+    .expect("valid header regex");
+
+    let caps = header_re
+        .captures(header)
+        .ok_or_else(|| format!("header {:?} must match 'type(scope)!: description'", header))?;
+
+    let commit_type = caps["type"].to_string();
+    if !allowed_types.iter().any(|t| *t == commit_type) {
+        return Err(format!(
+            "unknown commit type {:?}, expected one of {:?}",
+            commit_type, allowed_types
+        ));
+    }
+
+    let scope = caps.name("scope").map(|m| m.as_str().to_string());
+    let mut breaking = caps.name("breaking").is_some();
+    let description = caps["description"].to_string();
+
+    let footer_re = Regex::new(
+        r"^(?:(?P<token>BREAKING CHANGE|[A-Za-z-]+): (?P<value>.+)|(?P<token2>[A-Za-z-]+) #(?P<value2>.+))$",
+    )
+    .expect("valid footer regex");
+
+    let mut body_paragraphs: Vec<&str> = vec![];
+    let mut footers: Vec<(String, String)> = vec![];
+
+    for paragraph in remainder.split("\n\n").filter(|p| !p.trim().is_empty()) {
+        let is_footer_block = paragraph.lines().all(|line| footer_re.is_match(line.trim()));
+        if !is_footer_block {
+            body_paragraphs.push(paragraph);
+            continue;
+        }
+        for line in paragraph.lines() {
+            let caps = footer_re
+                .captures(line.trim())
+                .expect("line already matched footer_re");
+            let (token, value) = match caps.name("token") {
+                Some(token) => (token.as_str().to_string(), caps["value"].to_string()),
+                None => (caps["token2"].to_string(), caps["value2"].to_string()),
+            };
+            if token.eq_ignore_ascii_case("BREAKING CHANGE") || token == "BREAKING-CHANGE" {
+                breaking = true;
+            }
+            footers.push((token, value));
+        }
+    }
+
+    let body = if body_paragraphs.is_empty() {
+        None
+    } else {
+        Some(body_paragraphs.join("\n\n"))
+    };
+
+    Ok(ConventionalCommit {
+        commit_type,
+        scope,
+        breaking,
+        description,
+        body,
+        footers,
+    })
+}
+
+/// Parses `msg` as a Conventional Commit, and if it does not parse, re-prompts the model
+/// once with the validation error appended before giving up.
+pub fn ensure_conventional(
+    msg: String,
+    system_prompts: &[String],
+    user_prompt: &str,
+    tools: &ToolsCollection,
+    query_opts: &Opts,
+    allowed_types: &[&str],
+) -> Result<(String, ConventionalCommit), Box<dyn Error>> {
+    match parse(&msg, allowed_types) {
+        Ok(commit) => Ok((msg, commit)),
+        Err(e) => {
+            debug!("Commit message is not a valid Conventional Commit: {}", e);
+
+            let mut messages: Vec<Message> = system_prompts
+                .iter()
+                .map(|sp| make_message("system", sp.clone()))
+                .collect();
+            messages.push(make_message("user", user_prompt.to_string()));
+            messages.push(make_message("assistant", msg));
+            messages.push(make_message(
+                "user",
+                format!(
+                    "That is not a valid Conventional Commit: {}.  \
+                     Reply again with only the corrected commit message.",
+                    e
+                ),
+            ));
+
+            let response = post_request(messages, tools, query_opts)?;
+            let retried = extract_message(response, "No responses received")?;
+
+            let commit = parse(&retried, allowed_types)
+                .map_err(|e| format!("commit message is still not a valid Conventional Commit: {}", e))?;
+            Ok((retried, commit))
+        }
+    }
+}
+
+/// Appends instructions asking the model to emit a Conventional Commit to `prompt`.
+pub fn augment_prompt(prompt: String, allowed_types: &[&str]) -> String {
+    format!(
+        "{}\n\
+         Format the commit message as a Conventional Commit: `type(scope)!: description`, \
+         where type is one of {:?}, scope is optional, and `!` marks a breaking change.  \
+         Leave a blank line after the header, then an optional body, then footers of the \
+         form `Token: value` (use a `BREAKING CHANGE:` footer for breaking changes):\n",
+        prompt, allowed_types
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TYPES: &[&str] = &["feat", "fix"];
+
+    #[test]
+    fn parses_simple_header() {
+        let commit = parse("fix: correct off-by-one error", TYPES).unwrap();
+        assert_eq!(commit.commit_type, "fix");
+        assert_eq!(commit.scope, None);
+        assert!(!commit.breaking);
+        assert_eq!(commit.description, "correct off-by-one error");
+        assert_eq!(commit.body, None);
+        assert!(commit.footers.is_empty());
+    }
+
+    #[test]
+    fn parses_scope_and_bang_as_breaking() {
+        let commit = parse("feat(api)!: drop the v1 endpoints", TYPES).unwrap();
+        assert_eq!(commit.commit_type, "feat");
+        assert_eq!(commit.scope, Some("api".to_string()));
+        assert!(commit.breaking);
+    }
+
+    #[test]
+    fn parses_breaking_change_footer() {
+        let msg = "feat: add new widget\n\nBREAKING CHANGE: widget replaces gadget entirely";
+        let commit = parse(msg, TYPES).unwrap();
+        assert!(commit.breaking);
+        assert_eq!(
+            commit.footers,
+            vec![(
+                "BREAKING CHANGE".to_string(),
+                "widget replaces gadget entirely".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn parses_body_and_trailing_footer_separately() {
+        let msg = "fix: handle empty input\n\nThe parser used to panic on empty input.\n\nSigned-off-by: Jane Doe <jane@example.com>";
+        let commit = parse(msg, TYPES).unwrap();
+        assert_eq!(
+            commit.body,
+            Some("The parser used to panic on empty input.".to_string())
+        );
+        assert_eq!(
+            commit.footers,
+            vec![(
+                "Signed-off-by".to_string(),
+                "Jane Doe <jane@example.com>".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_type() {
+        let err = parse("chore: bump dependency", TYPES).unwrap_err();
+        assert!(err.contains("unknown commit type"));
+    }
+
+    #[test]
+    fn rejects_malformed_header() {
+        let err = parse("fixed a bug", TYPES).unwrap_err();
+        assert!(err.contains("must match"));
+    }
+}
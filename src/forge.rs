@@ -0,0 +1,229 @@
+/*
+ * git-chronicler
+ *
+ * Copyright (C) 2025 Giuseppe Scrivano <giuseppe@scrivano.org>
+ * git-chronicler is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * git-chronicler is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with git-chronicler.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+use crate::config::ForgeConfig;
+use crate::run_git_command;
+use log::{debug, info};
+use serde_json::json;
+use std::error::Error;
+
+/// A git forge that can host a pull request description for a branch summary.
+trait ForgeBackend {
+    fn find_pr_number(&self, owner: &str, repo: &str, head: &str, base: &str) -> Result<Option<u64>, Box<dyn Error>>;
+    fn create_pr(&self, owner: &str, repo: &str, head: &str, base: &str, title: &str, body: &str) -> Result<(), Box<dyn Error>>;
+    fn update_pr(&self, owner: &str, repo: &str, number: u64, title: &str, body: &str) -> Result<(), Box<dyn Error>>;
+}
+
+struct GithubBackend {
+    endpoint: String,
+    token: String,
+}
+
+impl ForgeBackend for GithubBackend {
+    fn find_pr_number(&self, owner: &str, repo: &str, head: &str, base: &str) -> Result<Option<u64>, Box<dyn Error>> {
+        let url = format!(
+            "{}/repos/{}/{}/pulls?head={}:{}&base={}&state=open",
+            self.endpoint, owner, repo, owner, head, base
+        );
+        let resp: Vec<serde_json::Value> = ureq::get(&url)
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Accept", "application/vnd.github+json")
+            .call()?
+            .into_json()?;
+        Ok(resp.first().and_then(|pr| pr["number"].as_u64()))
+    }
+
+    fn create_pr(&self, owner: &str, repo: &str, head: &str, base: &str, title: &str, body: &str) -> Result<(), Box<dyn Error>> {
+        let url = format!("{}/repos/{}/{}/pulls", self.endpoint, owner, repo);
+        ureq::post(&url)
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Accept", "application/vnd.github+json")
+            .send_json(json!({ "title": title, "body": body, "head": head, "base": base }))?;
+        Ok(())
+    }
+
+    fn update_pr(&self, owner: &str, repo: &str, number: u64, title: &str, body: &str) -> Result<(), Box<dyn Error>> {
+        let url = format!("{}/repos/{}/{}/pulls/{}", self.endpoint, owner, repo, number);
+        ureq::request("PATCH", &url)
+            .set("Authorization", &format!("Bearer {}", self.token))
+            .set("Accept", "application/vnd.github+json")
+            .send_json(json!({ "title": title, "body": body }))?;
+        Ok(())
+    }
+}
+
+struct GiteaBackend {
+    endpoint: String,
+    token: String,
+}
+
+impl ForgeBackend for GiteaBackend {
+    fn find_pr_number(&self, owner: &str, repo: &str, head: &str, base: &str) -> Result<Option<u64>, Box<dyn Error>> {
+        const PAGE_SIZE: u32 = 50;
+        let mut page = 1;
+        loop {
+            let url = format!(
+                "{}/repos/{}/{}/pulls?state=open&page={}&limit={}",
+                self.endpoint, owner, repo, page, PAGE_SIZE
+            );
+            let resp: Vec<serde_json::Value> = ureq::get(&url)
+                .set("Authorization", &format!("token {}", self.token))
+                .call()?
+                .into_json()?;
+
+            if resp.is_empty() {
+                return Ok(None);
+            }
+
+            let found = resp
+                .iter()
+                .find(|pr| pr["head"]["ref"] == head && pr["base"]["ref"] == base)
+                .and_then(|pr| pr["number"].as_u64());
+            if found.is_some() {
+                return Ok(found);
+            }
+
+            if (resp.len() as u32) < PAGE_SIZE {
+                return Ok(None);
+            }
+            page += 1;
+        }
+    }
+
+    fn create_pr(&self, owner: &str, repo: &str, head: &str, base: &str, title: &str, body: &str) -> Result<(), Box<dyn Error>> {
+        let url = format!("{}/repos/{}/{}/pulls", self.endpoint, owner, repo);
+        ureq::post(&url)
+            .set("Authorization", &format!("token {}", self.token))
+            .send_json(json!({ "title": title, "body": body, "head": head, "base": base }))?;
+        Ok(())
+    }
+
+    fn update_pr(&self, owner: &str, repo: &str, number: u64, title: &str, body: &str) -> Result<(), Box<dyn Error>> {
+        let url = format!("{}/repos/{}/{}/pulls/{}", self.endpoint, owner, repo, number);
+        ureq::request("PATCH", &url)
+            .set("Authorization", &format!("token {}", self.token))
+            .send_json(json!({ "title": title, "body": body }))?;
+        Ok(())
+    }
+}
+
+/// Extracts `(owner, repo)` from an `origin` remote URL, whether SSH- or HTTPS-style.
+fn parse_owner_repo(url: &str) -> Option<(String, String)> {
+    let url = url.trim().trim_end_matches(".git");
+    let path = if let Some(rest) = url.strip_prefix("git@") {
+        rest.splitn(2, ':').nth(1)?
+    } else if let Some(idx) = url.find("://") {
+        let rest = &url[idx + 3..];
+        rest.splitn(2, '/').nth(1)?
+    } else {
+        return None;
+    };
+
+    let mut parts = path.rsplitn(2, '/');
+    let repo = parts.next()?;
+    let owner = parts.next()?;
+    Some((owner.to_string(), repo.to_string()))
+}
+
+/// Publishes `summary` (first line = title, rest = body) as a pull request description
+/// for the current branch against `base`, creating or updating it as needed.
+pub fn publish(forge: &ForgeConfig, base: &str, summary: &str) -> Result<(), Box<dyn Error>> {
+    let token = forge.token.clone().ok_or(
+        "no forge token found; set the token environment variable or chronicler.forge-token",
+    )?;
+
+    let remote = run_git_command(vec!["remote", "get-url", "origin"])?;
+    let (owner, repo) = parse_owner_repo(&remote)
+        .ok_or("could not parse an owner/repo from the 'origin' remote URL")?;
+
+    let branch = run_git_command(vec!["rev-parse", "--abbrev-ref", "HEAD"])?
+        .trim()
+        .to_string();
+
+    let mut lines = summary.trim().splitn(2, '\n');
+    let title = lines.next().unwrap_or("").trim().to_string();
+    let body = lines.next().unwrap_or("").trim().to_string();
+
+    debug!("Publishing summary for {}/{} {}..{}", owner, repo, base, branch);
+
+    let backend: Box<dyn ForgeBackend> = match forge.kind.as_str() {
+        "github" => Box::new(GithubBackend {
+            endpoint: forge
+                .endpoint
+                .clone()
+                .unwrap_or_else(|| "https://api.github.com".to_string()),
+            token,
+        }),
+        "gitea" => Box::new(GiteaBackend {
+            endpoint: forge
+                .endpoint
+                .clone()
+                .ok_or("a gitea forge requires chronicler.forge-endpoint to be set")?,
+            token,
+        }),
+        other => return Err(format!("unknown forge type {:?}, expected 'github' or 'gitea'", other).into()),
+    };
+
+    match backend.find_pr_number(&owner, &repo, &branch, base)? {
+        Some(number) => {
+            info!("Updating pull request #{} on {}/{}", number, owner, repo);
+            backend.update_pr(&owner, &repo, number, &title, &body)?;
+        }
+        None => {
+            info!("Creating pull request on {}/{}", owner, repo);
+            backend.create_pr(&owner, &repo, &branch, base, &title, &body)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_ssh_remote() {
+        assert_eq!(
+            parse_owner_repo("git@github.com:giuseppe/git-chronicler.git"),
+            Some(("giuseppe".to_string(), "git-chronicler".to_string()))
+        );
+    }
+
+    #[test]
+    fn parses_https_remote() {
+        assert_eq!(
+            parse_owner_repo("https://github.com/giuseppe/git-chronicler.git"),
+            Some(("giuseppe".to_string(), "git-chronicler".to_string()))
+        );
+    }
+
+    #[test]
+    fn parses_https_remote_without_dot_git_suffix() {
+        assert_eq!(
+            parse_owner_repo("https://gitea.example.com/giuseppe/git-chronicler"),
+            Some(("giuseppe".to_string(), "git-chronicler".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_url_without_owner_repo() {
+        assert_eq!(parse_owner_repo("not-a-remote-url"), None);
+    }
+}
@@ -0,0 +1,232 @@
+/*
+ * git-chronicler
+ *
+ * Copyright (C) 2025 Giuseppe Scrivano <giuseppe@scrivano.org>
+ * git-chronicler is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * git-chronicler is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with git-chronicler.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+use crate::run_git_command;
+use log::{debug, info};
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Marker written at the top of every hook we install, so we can tell our own
+/// hooks apart from ones the user already had in place.
+const MARKER: &str = "# installed by git-chronicler, do not edit by hand";
+
+fn hooks_dir() -> Result<PathBuf, Box<dyn Error>> {
+    let dir = run_git_command(vec!["rev-parse", "--git-path", "hooks"])?;
+    Ok(PathBuf::from(dir.trim()))
+}
+
+fn chained_path(hook_path: &Path) -> PathBuf {
+    let mut chained = hook_path.to_path_buf();
+    let name = format!(
+        "{}.chronicler-chained",
+        chained.file_name().unwrap_or_default().to_string_lossy()
+    );
+    chained.set_file_name(name);
+    chained
+}
+
+fn make_executable(path: &Path) -> Result<(), Box<dyn Error>> {
+    let mut perms = fs::metadata(path)?.permissions();
+    perms.set_mode(0o755);
+    fs::set_permissions(path, perms)?;
+    Ok(())
+}
+
+/// Writes a hook script at `dir/name` that runs `body`. If a hook is already present
+/// and isn't one of ours, it is preserved as `<name>.chronicler-chained` and chained
+/// before `body`, so installing never silently drops an existing hook. If such a hook
+/// is present while `<name>.chronicler-chained` already exists, it fails instead.
+fn write_hook(dir: &Path, name: &str, body: &str) -> Result<(), Box<dyn Error>> {
+    let hook_path = dir.join(name);
+    let chained = chained_path(&hook_path);
+
+    if hook_path.exists() {
+        let existing = fs::read_to_string(&hook_path).unwrap_or_default();
+        if !existing.contains(MARKER) {
+            if chained.exists() {
+                return Err(format!(
+                    "{:?} was not installed by git-chronicler and {:?} already exists; refusing to overwrite either",
+                    hook_path, chained
+                )
+                .into());
+            }
+            debug!("Chaining existing {} hook to {:?}", name, chained);
+            fs::copy(&hook_path, &chained)?;
+            make_executable(&chained)?;
+        }
+    }
+
+    let chain_call = if chained.exists() {
+        format!(
+            "\"$(dirname \"$0\")/{}\" \"$@\" || exit $?\n",
+            chained.file_name().unwrap().to_string_lossy()
+        )
+    } else {
+        String::new()
+    };
+
+    let script = format!("#!/bin/sh\n{}\n{}{}\n", MARKER, chain_call, body);
+    fs::write(&hook_path, script)?;
+    make_executable(&hook_path)?;
+    Ok(())
+}
+
+/// Removes `name` from `dir` if it's one of our own hooks, restoring a chained
+/// pre-existing hook if there was one. Returns whether a hook was actually removed.
+fn remove_hook(dir: &Path, name: &str) -> Result<bool, Box<dyn Error>> {
+    let hook_path = dir.join(name);
+    if !hook_path.exists() {
+        return Ok(false);
+    }
+
+    let existing = fs::read_to_string(&hook_path).unwrap_or_default();
+    if !existing.contains(MARKER) {
+        debug!("Leaving {} hook alone, it wasn't installed by us", name);
+        return Ok(false);
+    }
+
+    let chained = chained_path(&hook_path);
+    if chained.exists() {
+        fs::rename(&chained, &hook_path)?;
+    } else {
+        fs::remove_file(&hook_path)?;
+    }
+    Ok(true)
+}
+
+/// Installs the `prepare-commit-msg` hook, and `commit-msg` too when `commit_msg` is set.
+pub fn install(commit_msg: bool) -> Result<(), Box<dyn Error>> {
+    let dir = hooks_dir()?;
+    fs::create_dir_all(&dir)?;
+    let bin = env::current_exe()?;
+    let bin = bin.to_string_lossy();
+
+    write_hook(
+        &dir,
+        "prepare-commit-msg",
+        &format!("exec \"{}\" hook-prepare-commit-msg \"$@\"", bin),
+    )?;
+
+    if commit_msg {
+        write_hook(
+            &dir,
+            "commit-msg",
+            &format!("exec \"{}\" hook-commit-msg \"$@\"", bin),
+        )?;
+    } else if remove_hook(&dir, "commit-msg")? {
+        info!("commit-msg hook was previously installed but --commit-msg wasn't given; removing it");
+    }
+
+    Ok(())
+}
+
+/// Removes any hooks we installed, restoring a chained pre-existing hook if there was one.
+pub fn uninstall() -> Result<(), Box<dyn Error>> {
+    let dir = hooks_dir()?;
+    remove_hook(&dir, "prepare-commit-msg")?;
+    remove_hook(&dir, "commit-msg")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn write_hook_creates_fresh_hook_without_chaining() {
+        let dir = tempfile::tempdir().unwrap();
+        write_hook(dir.path(), "prepare-commit-msg", "echo body").unwrap();
+
+        let script = fs::read_to_string(dir.path().join("prepare-commit-msg")).unwrap();
+        assert!(script.contains(MARKER));
+        assert!(script.contains("echo body"));
+        assert!(!dir.path().join("prepare-commit-msg.chronicler-chained").exists());
+    }
+
+    #[test]
+    fn write_hook_chains_pre_existing_foreign_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        let hook_path = dir.path().join("prepare-commit-msg");
+        fs::write(&hook_path, "#!/bin/sh\necho existing\n").unwrap();
+        make_executable(&hook_path).unwrap();
+
+        write_hook(dir.path(), "prepare-commit-msg", "echo body").unwrap();
+
+        let chained = fs::read_to_string(chained_path(&hook_path)).unwrap();
+        assert_eq!(chained, "#!/bin/sh\necho existing\n");
+
+        let script = fs::read_to_string(&hook_path).unwrap();
+        assert!(script.contains("prepare-commit-msg.chronicler-chained"));
+        assert!(script.contains("echo body"));
+    }
+
+    #[test]
+    fn write_hook_is_idempotent_on_our_own_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        write_hook(dir.path(), "prepare-commit-msg", "echo body").unwrap();
+        write_hook(dir.path(), "prepare-commit-msg", "echo body").unwrap();
+
+        assert!(!dir.path().join("prepare-commit-msg.chronicler-chained").exists());
+        let script = fs::read_to_string(dir.path().join("prepare-commit-msg")).unwrap();
+        assert_eq!(script.matches(MARKER).count(), 1);
+    }
+
+    #[test]
+    fn remove_hook_deletes_our_hook_with_no_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        write_hook(dir.path(), "commit-msg", "echo body").unwrap();
+
+        remove_hook(dir.path(), "commit-msg").unwrap();
+
+        assert!(!dir.path().join("commit-msg").exists());
+    }
+
+    #[test]
+    fn remove_hook_restores_chained_hook() {
+        let dir = tempfile::tempdir().unwrap();
+        let hook_path = dir.path().join("prepare-commit-msg");
+        fs::write(&hook_path, "#!/bin/sh\necho existing\n").unwrap();
+        make_executable(&hook_path).unwrap();
+        write_hook(dir.path(), "prepare-commit-msg", "echo body").unwrap();
+
+        remove_hook(dir.path(), "prepare-commit-msg").unwrap();
+
+        let restored = fs::read_to_string(&hook_path).unwrap();
+        assert_eq!(restored, "#!/bin/sh\necho existing\n");
+        assert!(!chained_path(&hook_path).exists());
+    }
+
+    #[test]
+    fn remove_hook_leaves_foreign_hook_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let hook_path = dir.path().join("prepare-commit-msg");
+        fs::write(&hook_path, "#!/bin/sh\necho existing\n").unwrap();
+
+        remove_hook(dir.path(), "prepare-commit-msg").unwrap();
+
+        assert!(hook_path.exists());
+        assert_eq!(
+            fs::read_to_string(&hook_path).unwrap(),
+            "#!/bin/sh\necho existing\n"
+        );
+    }
+}
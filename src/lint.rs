@@ -0,0 +1,192 @@
+/*
+ * git-chronicler
+ *
+ * Copyright (C) 2025 Giuseppe Scrivano <giuseppe@scrivano.org>
+ * git-chronicler is free software; you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation; either version 2 of the License, or
+ * (at your option) any later version.
+ *
+ * git-chronicler is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with git-chronicler.  If not, see <http://www.gnu.org/licenses/>.
+ *
+ */
+
+use regex::Regex;
+use std::fmt;
+
+/// Column limits enforced by `lint`. Mirrors what the AI prompts already ask for.
+pub struct LintConfig {
+    pub subject_max_len: usize,
+    pub body_wrap: usize,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        LintConfig {
+            subject_max_len: 52,
+            body_wrap: 80,
+        }
+    }
+}
+
+/// A single deterministic lint finding.
+pub struct Violation {
+    pub rule: &'static str,
+    pub line: usize,
+    pub message: String,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}: {}", self.rule, self.line, self.message)
+    }
+}
+
+/// Runs the deterministic rule set against a commit message, without calling the model.
+pub fn lint(msg: &str, config: &LintConfig) -> Vec<Violation> {
+    let mut violations = vec![];
+    let lines: Vec<&str> = msg.lines().collect();
+
+    let subject = match lines.first() {
+        Some(s) => *s,
+        None => {
+            violations.push(Violation {
+                rule: "empty-message",
+                line: 1,
+                message: "commit message is empty".to_string(),
+            });
+            return violations;
+        }
+    };
+
+    if subject.chars().count() > config.subject_max_len {
+        violations.push(Violation {
+            rule: "subject-too-long",
+            line: 1,
+            message: format!(
+                "subject line is {} columns, expected at most {}",
+                subject.chars().count(),
+                config.subject_max_len
+            ),
+        });
+    }
+
+    if subject.trim_end().ends_with('.') {
+        violations.push(Violation {
+            rule: "subject-ends-with-period",
+            line: 1,
+            message: "subject line should not end with a period".to_string(),
+        });
+    }
+
+    if let Some(first_word) = subject.split_whitespace().next() {
+        let lower = first_word.to_lowercase();
+        let looks_non_imperative =
+            lower.ends_with("ed") || lower.ends_with("ing") || (lower.ends_with('s') && !lower.ends_with("ss"));
+        if looks_non_imperative {
+            violations.push(Violation {
+                rule: "non-imperative-mood",
+                line: 1,
+                message: format!(
+                    "leading verb {:?} doesn't look imperative (e.g. use \"Add\" rather than \"Added\"/\"Adds\"/\"Adding\")",
+                    first_word
+                ),
+            });
+        }
+    }
+
+    if lines.len() > 1 {
+        if !lines[1].trim().is_empty() {
+            violations.push(Violation {
+                rule: "missing-blank-line",
+                line: 2,
+                message: "expected a blank line between the subject and the body".to_string(),
+            });
+        }
+
+        let trailer_regex = Regex::new(r"^[A-Za-z0-9-]+:\s+.+$").expect("valid trailer regex");
+        for (i, line) in lines.iter().enumerate().skip(2) {
+            if trailer_regex.is_match(line.trim()) {
+                continue;
+            }
+            if line.chars().count() > config.body_wrap {
+                violations.push(Violation {
+                    rule: "body-too-wide",
+                    line: i + 1,
+                    message: format!(
+                        "line is {} columns, expected at most {}",
+                        line.chars().count(),
+                        config.body_wrap
+                    ),
+                });
+            }
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rules(msg: &str) -> Vec<&'static str> {
+        lint(msg, &LintConfig::default())
+            .iter()
+            .map(|v| v.rule)
+            .collect()
+    }
+
+    #[test]
+    fn accepts_well_formed_message() {
+        assert!(rules("Add support for widgets\n\nExplains why widgets are needed.").is_empty());
+    }
+
+    #[test]
+    fn flags_overlong_subject() {
+        let subject = "Add ".to_string() + &"x".repeat(80);
+        assert_eq!(rules(&subject), vec!["subject-too-long"]);
+    }
+
+    #[test]
+    fn flags_subject_ending_with_period() {
+        assert_eq!(rules("Add widget support."), vec!["subject-ends-with-period"]);
+    }
+
+    #[test]
+    fn flags_non_imperative_mood() {
+        assert_eq!(rules("Added widget support"), vec!["non-imperative-mood"]);
+    }
+
+    #[test]
+    fn flags_missing_blank_line() {
+        assert_eq!(
+            rules("Add widget support\nright after the subject"),
+            vec!["missing-blank-line"]
+        );
+    }
+
+    #[test]
+    fn ignores_trailers_when_checking_body_width() {
+        let long_trailer = format!("Signed-off-by: {}", "x".repeat(90));
+        let msg = format!("Add widget support\n\n{}", long_trailer);
+        assert!(rules(&msg).is_empty());
+    }
+
+    #[test]
+    fn flags_overwide_body_line() {
+        let msg = format!("Add widget support\n\n{}", "x".repeat(90));
+        assert_eq!(rules(&msg), vec!["body-too-wide"]);
+    }
+
+    #[test]
+    fn flags_empty_message() {
+        assert_eq!(rules(""), vec!["empty-message"]);
+    }
+}
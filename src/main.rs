@@ -19,22 +19,35 @@
 
 use clap::{Parser, Subcommand};
 use codehawk::openai::{
-    Message, Opts, ToolCallback, ToolItem, ToolsCollection, make_message, post_request,
+    Message, Opts, Response, ToolCallback, ToolItem, ToolsCollection, make_message, post_request,
 };
 use env_logger::Env;
 use log::{debug, info, trace};
 use regex::Regex;
 use serde::Deserialize;
 use std::error::Error;
+use std::fs;
 use std::io::Write;
 use std::process::{Command, Stdio};
 
+mod batch;
+mod changelog;
+mod config;
+mod conventional;
+mod forge;
+mod hooks;
+mod lint;
+
 const DEFAULT_OPENAI_URL: &str = "https://openrouter.ai/api/v1/chat/completions";
 const MODEL: &str = "google/gemini-2.5-pro-preview-03-25";
 const MAX_TOKENS: u32 = 16384;
 
 /// Creates a prompt for the AI model to improve an existing commit message.
-fn inline_prompt() -> String {
+fn inline_prompt(override_prompt: Option<&str>) -> String {
+    if let Some(p) = override_prompt {
+        debug!("Using configured inline prompt override");
+        return p.to_owned();
+    }
     debug!("Creating inline prompt for commit message improvement");
     "Improve the git commit message for the patch and add any missing information you get from the code.  \
      Explain why a change is done, not what was changed.  Keep the first line below 52 columns and next ones under 80 columns.  \
@@ -43,7 +56,11 @@ fn inline_prompt() -> String {
 }
 
 /// Creates a prompt for the AI model to write a new commit message.
-fn write_prompt() -> String {
+fn write_prompt(override_prompt: Option<&str>) -> String {
+    if let Some(p) = override_prompt {
+        debug!("Using configured write prompt override");
+        return p.to_owned();
+    }
     debug!("Creating write prompt for new commit message");
     "Write the git commit message for the patch and add any information you get from the code.  \
      Explain why a change is done, not what was changed.  Keep the first line below 52 columns and next ones under 80 columns.  \
@@ -51,7 +68,11 @@ fn write_prompt() -> String {
 }
 
 /// Creates a prompt for the AI model to check an existing commit message for errors.
-fn check_prompt() -> String {
+fn check_prompt(override_prompt: Option<&str>) -> String {
+    if let Some(p) = override_prompt {
+        debug!("Using configured check prompt override");
+        return p.to_owned();
+    }
     debug!("Creating check prompt for commit message validation");
     "Report any mistake you see in the commit log message.  \
      If the input contains a significant error or discrepancy, the first line of the returned message must only contain the string ERROR and nothing more.  \
@@ -67,7 +88,11 @@ fn tool_list_all_files(_params_str: &String) -> Result<String, Box<dyn Error>> {
 }
 
 /// Creates a prompt to ask for a summary of the current branch
-fn summary_prompt() -> String {
+fn summary_prompt(override_prompt: Option<&str>) -> String {
+    if let Some(p) = override_prompt {
+        debug!("Using configured summary prompt override");
+        return p.to_owned();
+    }
     debug!("Creating summary prompt");
 
     "Summarize the changes in the git commits, give more importance to the commit messages.\n \
@@ -158,7 +183,7 @@ fn initialize_tools() -> ToolsCollection {
 }
 
 /// Run a git command and retrieve the stdout
-fn run_git_command(args: Vec<&str>) -> Result<String, Box<dyn Error>> {
+pub(crate) fn run_git_command(args: Vec<&str>) -> Result<String, Box<dyn Error>> {
     debug!("Running git command {:?}", args);
 
     let mut input = Command::new("git");
@@ -211,6 +236,11 @@ fn get_last_commit() -> Result<String, Box<dyn Error>> {
     run_git_command(vec!["log", "-p", "-1"])
 }
 
+/// Retrieves only the last commit's log message, without the patch.
+fn get_last_commit_message() -> Result<String, Box<dyn Error>> {
+    run_git_command(vec!["log", "-1", "--pretty=format:%B"])
+}
+
 /// Retrieves the diff of changes using `git diff`.
 fn get_diff(cached: bool) -> Result<String, Box<dyn Error>> {
     let mut args: Vec<&str> = vec!["diff", "-U50"];
@@ -331,18 +361,69 @@ fn amend_commit(commit_msg: &str) -> Result<(), Box<dyn Error>> {
     Ok(())
 }
 
-/// Checks the AI's response for the 'check' command.
+/// Checks the AI's response for the 'check' command, returning the AI's explanation as
+/// an error when the message is flagged with a leading `ERROR` line. Never prints:
+/// callers decide what to do with the success and failure paths.
 fn check_commit(msg: &str) -> Result<(), Box<dyn Error>> {
     debug!("Checking commit message for errors");
     if let Some(msg) = msg.strip_prefix("ERROR\n") {
-        eprintln!("{}", msg.trim());
-        return Err("wrong commit message".into());
+        return Err(msg.trim().into());
     }
     debug!("Commit message passed validation check");
-    println!("{}", &msg);
     Ok(())
 }
 
+/// Runs the local deterministic lint pass against `message`, printing and failing on
+/// any violation. Shared by `Check` and the installed `commit-msg` hook so both reject
+/// an obviously malformed message before ever paying for an AI round trip.
+fn lint_or_fail(message: &str) -> Result<(), Box<dyn Error>> {
+    let violations = lint::lint(message, &lint::LintConfig::default());
+    if !violations.is_empty() {
+        for violation in &violations {
+            eprintln!("{}", violation);
+        }
+        return Err("commit message failed lint checks".into());
+    }
+    Ok(())
+}
+
+/// Builds the style prompt, tools and query options shared by every AI request: the
+/// last 100 commit messages as a style guide, the tool callbacks, and the model/endpoint
+/// settings resolved from `config`.
+fn build_ai_context(
+    config: &config::Config,
+    model: &str,
+) -> Result<(String, ToolsCollection, Opts), Box<dyn Error>> {
+    let last_git_messages = get_last_git_messages(100)?;
+    let last_git_messages_json = serde_json::to_string(&last_git_messages)?;
+    let style_prompt = format!(
+        "Follow the style of these git commit messages: {}",
+        last_git_messages_json
+    );
+
+    let tools = initialize_tools();
+    let query_opts = Opts {
+        max_tokens: Some(config.max_tokens),
+        model: model.to_string(),
+        endpoint: config.endpoint.clone(),
+    };
+
+    Ok((style_prompt, tools, query_opts))
+}
+
+/// Extracts the concatenated message content from an AI response's choices, failing
+/// with `err_msg` if none were returned. Shared by every call site that issues a
+/// `post_request` across the crate.
+pub(crate) fn extract_message(response: Response, err_msg: &str) -> Result<String, Box<dyn Error>> {
+    match response.choices {
+        Some(choices) if !choices.is_empty() => {
+            debug!("Received {} choices from AI", choices.len());
+            Ok(choices.into_iter().map(|c| c.message.content).collect())
+        }
+        _ => Err(err_msg.into()),
+    }
+}
+
 #[derive(Parser, Debug)]
 #[clap(version = env!("CARGO_PKG_VERSION"))]
 struct CliOpts {
@@ -352,14 +433,14 @@ struct CliOpts {
     #[clap(long)]
     /// Override the model to use
     model: Option<String>,
-    #[clap(long, default_value = DEFAULT_OPENAI_URL)]
+    #[clap(long)]
     /// Override the endpoint URL to use
-    endpoint: String,
+    endpoint: Option<String>,
     #[clap(subcommand)]
     command: SubCommand,
 }
 
-#[derive(Debug, Subcommand)]
+#[derive(Debug, Clone, Subcommand)]
 enum SubCommand {
     /// Write a commit message
     Write {
@@ -374,16 +455,82 @@ enum SubCommand {
         /// Modify the message before commit
         #[clap(short, long)]
         interactive: bool,
+
+        /// Enforce Conventional Commits and validate the result before committing
+        #[clap(long)]
+        conventional: bool,
     },
     /// Fixup the current commit message inline
-    Fixup,
+    Fixup {
+        /// Enforce Conventional Commits and validate the result before committing
+        #[clap(long)]
+        conventional: bool,
+
+        /// Improve every non-merge commit in <base>..HEAD instead of only HEAD
+        #[clap(long)]
+        range: Option<String>,
+
+        /// With --range, print the before/after messages without rewriting history
+        #[clap(long)]
+        dry_run: bool,
+    },
     /// Check if the commit message describes correctly the patch
-    Check,
+    Check {
+        /// Only run the local rule-based lint, without calling the model
+        #[clap(long)]
+        offline: bool,
+    },
     /// Create a summary of the current branch
     Summary {
-        /// Base branch
-        base: String,
+        /// Base branch. Falls back to the configured default base if omitted.
+        base: Option<String>,
+
+        /// Publish the summary as the description of a pull request on the configured forge
+        #[clap(long)]
+        publish: bool,
+    },
+    /// Generate release notes grouped by Conventional Commit type
+    Changelog {
+        /// Start of the range. Defaults to the most recent tag reachable from `to`.
+        from: Option<String>,
+
+        /// End of the range
+        #[clap(long, default_value = "HEAD")]
+        to: String,
+
+        /// Insert the generated section into CHANGELOG.md under the changelog marker instead of printing it
+        #[clap(long)]
+        write: bool,
+    },
+    /// Install or remove the git hooks that run chronicler automatically
+    Hooks {
+        #[clap(subcommand)]
+        action: HooksAction,
     },
+    /// Internal: invoked by the installed `prepare-commit-msg` hook
+    #[clap(hide = true)]
+    HookPrepareCommitMsg {
+        file: String,
+        #[clap(default_value = "")]
+        source: String,
+        #[clap(default_value = "")]
+        sha: String,
+    },
+    /// Internal: invoked by the installed `commit-msg` hook
+    #[clap(hide = true)]
+    HookCommitMsg { file: String },
+}
+
+#[derive(Debug, Clone, Subcommand)]
+enum HooksAction {
+    /// Install the hooks into .git/hooks
+    Install {
+        /// Also install the commit-msg hook, aborting commits the AI flags as wrong
+        #[clap(long)]
+        commit_msg: bool,
+    },
+    /// Remove the hooks we installed, restoring any hook we chained
+    Uninstall,
 }
 
 /// Main entry point for the git-chronicler application.
@@ -399,113 +546,358 @@ fn main() -> Result<(), Box<dyn Error>> {
     let opts = CliOpts::parse();
     debug!("Command line options parsed");
 
-    let model = opts.model.clone().unwrap_or_else(|| MODEL.to_string());
+    let config = config::load(
+        opts.model.clone(),
+        opts.endpoint.clone(),
+        opts.max_tokens,
+    );
+    let model = config.model.clone();
     debug!("Using model: {}", model);
-    debug!("Using endpoint: {}", opts.endpoint);
+    debug!("Using endpoint: {}", config.endpoint);
+
+    let conventional_types: Vec<&str> = config
+        .conventional_types
+        .as_ref()
+        .map(|types| types.iter().map(|t| t.as_str()).collect())
+        .unwrap_or_else(|| conventional::DEFAULT_TYPES.to_vec());
+
+    if let SubCommand::Hooks { action } = opts.command.clone() {
+        info!("Running hooks command");
+        match action {
+            HooksAction::Install { commit_msg } => hooks::install(commit_msg)?,
+            HooksAction::Uninstall => hooks::uninstall()?,
+        }
+        return Ok(());
+    }
+
+    if let SubCommand::HookPrepareCommitMsg { file, source, .. } = opts.command.clone() {
+        debug!("prepare-commit-msg hook invoked (source={:?})", source);
+
+        // `source` is empty for a plain commit and "template" when -t/commit.template
+        // supplied the buffer: both are the Write-equivalent case (a new commit from
+        // the staged diff). "commit" is set by `commit --amend` (and `cherry-pick`/`-c`):
+        // the Fixup-equivalent case, improving the message of an existing commit.
+        // Anything else (merge, squash, message) already has a message the user chose
+        // on purpose, so the hook leaves it alone.
+        let patch = if source.is_empty() || source == "template" {
+            match get_diff(true) {
+                Ok(d) => d,
+                Err(_) => {
+                    debug!("No staged changes, leaving commit message buffer untouched");
+                    return Ok(());
+                }
+            }
+        } else if source == "commit" {
+            get_last_commit()?
+        } else {
+            debug!("Commit message already provided (source={:?}), leaving it alone", source);
+            return Ok(());
+        };
+
+        let mut prompt = if source == "commit" {
+            inline_prompt(config.inline_prompt.as_deref())
+        } else {
+            write_prompt(config.write_prompt.as_deref())
+        };
+        if config.conventional {
+            prompt = conventional::augment_prompt(prompt, &conventional_types);
+        }
+
+        let (style_prompt, tools, query_opts) = build_ai_context(&config, &model)?;
+        let system_prompts = vec![patch, style_prompt];
+
+        let messages: Vec<Message> = system_prompts
+            .iter()
+            .map(|sp| make_message("system", sp.clone()))
+            .chain(std::iter::once(make_message("user", prompt.clone())))
+            .collect();
+
+        let response = post_request(messages, &tools, &query_opts)?;
+        let msg = extract_message(response, "No responses received")?;
+
+        let msg = if config.conventional {
+            debug!("Validating Conventional Commit format for prepare-commit-msg hook");
+            let (msg, _) = conventional::ensure_conventional(
+                msg,
+                &system_prompts,
+                &prompt,
+                &tools,
+                &query_opts,
+                &conventional_types,
+            )?;
+            msg
+        } else {
+            msg
+        };
+
+        fs::write(&file, msg)?;
+        return Ok(());
+    }
+
+    if let SubCommand::HookCommitMsg { file } = opts.command.clone() {
+        debug!("commit-msg hook invoked");
+        let candidate = fs::read_to_string(&file)?;
+
+        if let Err(e) = lint_or_fail(&candidate) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+
+        if config.conventional {
+            if let Err(e) = conventional::parse(&candidate, &conventional_types) {
+                eprintln!("{}", e);
+                std::process::exit(1);
+            }
+        }
+
+        let diff = get_diff(true).unwrap_or_default();
+        let prompt = format!("{}\n{}", check_prompt(config.check_prompt.as_deref()), candidate);
+
+        let (style_prompt, tools, query_opts) = build_ai_context(&config, &model)?;
+
+        let messages = vec![
+            make_message("system", diff),
+            make_message("system", style_prompt),
+            make_message("user", prompt),
+        ];
+
+        let response = post_request(messages, &tools, &query_opts)?;
+        let msg = extract_message(response, "No responses received")?;
+
+        if let Err(e) = check_commit(&msg) {
+            eprintln!("{}", e);
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    if let SubCommand::Check { offline } = opts.command.clone() {
+        info!("Running check command to validate commit message");
+        let message = get_last_commit_message()?;
+
+        lint_or_fail(&message)?;
+
+        if offline {
+            debug!("Offline mode, skipping the AI check");
+            return Ok(());
+        }
+
+        let patch = get_last_commit()?;
+        let prompt = check_prompt(config.check_prompt.as_deref());
+
+        let (style_prompt, tools, query_opts) = build_ai_context(&config, &model)?;
+
+        let messages = vec![
+            make_message("system", patch),
+            make_message("system", style_prompt),
+            make_message("user", prompt),
+        ];
+
+        let response = post_request(messages, &tools, &query_opts)?;
+        let msg = extract_message(response, "No responses received")?;
+
+        if let Err(e) = check_commit(&msg) {
+            eprintln!("{}", e);
+            return Err("wrong commit message".into());
+        }
+        println!("{}", &msg);
+        return Ok(());
+    }
+
+    if let SubCommand::Changelog { from, to, write } = opts.command.clone() {
+        info!("Running changelog command for {}..{}", from.as_deref().unwrap_or("<last tag>"), to);
+
+        let (style_prompt, tools, query_opts) = build_ai_context(&config, &model)?;
+
+        let section = changelog::generate(
+            from,
+            Some(to),
+            &conventional_types,
+            &style_prompt,
+            &tools,
+            &query_opts,
+        )?;
+
+        if write {
+            let marker = config
+                .changelog_marker
+                .as_deref()
+                .unwrap_or(changelog::DEFAULT_MARKER);
+            changelog::write_to_file("CHANGELOG.md", marker, &section)?;
+        } else {
+            println!("{}", section);
+        }
+
+        return Ok(());
+    }
 
+    if let SubCommand::Fixup {
+        conventional,
+        range: Some(range),
+        dry_run,
+    } = opts.command.clone()
+    {
+        info!("Running batch fixup over {}..HEAD", range);
+        let enforce_conventional = conventional || config.conventional;
+        let mut prompt = inline_prompt(config.inline_prompt.as_deref());
+        if enforce_conventional {
+            prompt = conventional::augment_prompt(prompt, &conventional_types);
+        }
+
+        let (style_prompt, tools, query_opts) = build_ai_context(&config, &model)?;
+
+        let range_spec = format!("{}..HEAD", range);
+        let conventional_check = if enforce_conventional {
+            Some(conventional_types.as_slice())
+        } else {
+            None
+        };
+        let rewritten = batch::improve_range(
+            &range_spec,
+            &prompt,
+            &style_prompt,
+            &tools,
+            &query_opts,
+            conventional_check,
+        )?;
+
+        if dry_run {
+            for commit in &rewritten {
+                println!("commit {}", commit.hash);
+                println!("--- before ---\n{}", commit.old_message.trim());
+                println!("--- after ----\n{}\n", commit.new_message.trim());
+            }
+        } else {
+            batch::apply(&range_spec, &rewritten)?;
+            info!("Rewrote {} commit messages in {}", rewritten.len(), range_spec);
+        }
+
+        return Ok(());
+    }
+
+    let mut summary_base: Option<String> = None;
     let (prompt, patch) = match opts.command {
-        SubCommand::Fixup => {
+        SubCommand::Fixup { conventional, .. } => {
             info!("Running fixup command to improve existing commit message");
-            (inline_prompt(), get_last_commit()?)
-        }
-        SubCommand::Check => {
-            info!("Running check command to validate commit message");
-            (check_prompt(), get_last_commit()?)
+            let mut prompt = inline_prompt(config.inline_prompt.as_deref());
+            if conventional || config.conventional {
+                prompt = conventional::augment_prompt(prompt, &conventional_types);
+            }
+            (prompt, get_last_commit()?)
         }
+        SubCommand::Check { .. } => unreachable!("handled above"),
         SubCommand::Write {
             signoff,
             cached,
             interactive,
+            conventional,
         } => {
             info!("Running write command to create new commit message");
             debug!(
                 "Write options: signoff={}, cached={}, interactive={}",
                 signoff, cached, interactive
             );
-            (write_prompt(), get_diff(cached)?)
+            let mut prompt = write_prompt(config.write_prompt.as_deref());
+            if conventional || config.conventional {
+                prompt = conventional::augment_prompt(prompt, &conventional_types);
+            }
+            (prompt, get_diff(cached)?)
         }
-        SubCommand::Summary { ref base } => {
+        SubCommand::Summary { ref base, .. } => {
             info!("Running summary command");
+            let base = base
+                .clone()
+                .or_else(|| config.base.clone())
+                .ok_or("no base branch given and no default base configured")?;
             debug!("Summary options: base={}", base);
-            (summary_prompt(), get_branch_patches(base)?)
+            let patch = get_branch_patches(&base)?;
+            summary_base = Some(base);
+            (summary_prompt(config.summary_prompt.as_deref()), patch)
         }
+        SubCommand::Changelog { .. } => unreachable!("handled above"),
+        SubCommand::Hooks { .. } => unreachable!("handled above"),
+        SubCommand::HookPrepareCommitMsg { .. } => unreachable!("handled above"),
+        SubCommand::HookCommitMsg { .. } => unreachable!("handled above"),
     };
 
     let prompt = prompt.to_string();
     debug!("Using prompt: {}", prompt);
 
-    let last_git_messages = get_last_git_messages(100)?;
-    let last_git_messages_json = serde_json::to_string(&last_git_messages)?;
-    let git_history_prompt = format!(
-        "Follow the style of these git commit messages: {}",
-        last_git_messages_json
-    );
-
+    let (git_history_prompt, tools, query_opts) = build_ai_context(&config, &model)?;
     let system_prompts: Vec<String> = vec![patch.to_string(), git_history_prompt];
     debug!("System prompt size: {} bytes", system_prompts[0].len());
 
-    let tools = initialize_tools();
-
-    let max_tokens = opts.max_tokens.unwrap_or(MAX_TOKENS);
-    debug!("Max tokens: {}", max_tokens);
-
-    let query_opts = Opts {
-        max_tokens: Some(max_tokens),
-        model: model,
-        endpoint: opts.endpoint.clone(),
-    };
-
     info!("Sending request to AI service");
 
     let mut messages: Vec<Message> = vec![];
     debug!("Using {} system prompts", system_prompts.len());
-    for sp in system_prompts {
+    for sp in &system_prompts {
         messages.push(make_message("system", sp.clone()));
     }
     messages.push(make_message("user", prompt.clone()));
 
-    let response = match post_request(messages, &tools, &query_opts) {
-        Ok(resp) => resp,
-        Err(e) => {
-            return Err(e);
-        }
-    };
-
-    let msg: String = match response.choices {
-        Some(choices) if !choices.is_empty() => {
-            debug!("Received {} choices from AI", choices.len());
-            choices
-                .into_iter()
-                .map(|choice| choice.message.content)
-                .collect()
+    let response = post_request(messages, &tools, &query_opts)?;
+    let msg = extract_message(response, "No responses received")?;
+
+    let msg = match opts.command {
+        SubCommand::Fixup { conventional, .. } if conventional || config.conventional => {
+            debug!("Validating Conventional Commit format for fixup");
+            let (msg, _) = conventional::ensure_conventional(
+                msg,
+                &system_prompts,
+                &prompt,
+                &tools,
+                &query_opts,
+                &conventional_types,
+            )?;
+            msg
         }
-        _ => {
-            return Err("No responses received".into());
+        SubCommand::Write { conventional, .. } if conventional || config.conventional => {
+            debug!("Validating Conventional Commit format for write");
+            let (msg, _) = conventional::ensure_conventional(
+                msg,
+                &system_prompts,
+                &prompt,
+                &tools,
+                &query_opts,
+                &conventional_types,
+            )?;
+            msg
         }
+        _ => msg,
     };
 
     info!("AI response received, processing command");
     match opts.command {
-        SubCommand::Fixup => {
+        SubCommand::Fixup { .. } => {
             debug!("Processing fixup command");
             amend_commit(&msg)?;
         }
-        SubCommand::Check => {
-            debug!("Processing check command");
-            check_commit(&msg)?;
-        }
+        SubCommand::Check { .. } => unreachable!("handled above"),
         SubCommand::Write {
             signoff,
             cached,
             interactive,
+            ..
         } => {
             debug!("Processing write command");
             write_commit(&msg, signoff, cached, interactive)?;
         }
-        SubCommand::Summary { .. } => {
+        SubCommand::Summary { publish, .. } => {
             debug!("Processing summary command");
             println!("{}", msg);
+            if publish {
+                let forge_config = config.forge.as_ref().ok_or(
+                    "no forge configured; set chronicler.forge-type or the [forge] table in .chronicler.toml",
+                )?;
+                let base = summary_base.ok_or("no base branch resolved for the summary")?;
+                forge::publish(forge_config, &base, &msg)?;
+            }
         }
+        SubCommand::Changelog { .. } => unreachable!("handled above"),
+        SubCommand::Hooks { .. } => unreachable!("handled above"),
+        SubCommand::HookPrepareCommitMsg { .. } => unreachable!("handled above"),
+        SubCommand::HookCommitMsg { .. } => unreachable!("handled above"),
     };
 
     info!("Command completed successfully");